@@ -1,21 +1,164 @@
-use crossterm::{cursor, style, terminal, QueueableCommand, Result};
+use crossterm::{
+	cursor,
+	event::{self, Event, KeyCode},
+	style, terminal, QueueableCommand, Result,
+};
 use std::io::{Stdout, Write};
 
 #[cfg(windows)]
 use winapi::{
 	shared::minwindef,
-	um::{errhandlingapi, processenv, winbase, wincon, wincontypes, winuser},
+	um::{consoleapi, errhandlingapi, processenv, winbase, wincon},
 };
 
-const WIDTH: u16 = 200;
-const HEIGHT: u16 = 100;
-
 const SPAWN_CHANCE: f32 = 1f32 / 100f32;
 
 const CELL: &[u8] = &[0xE2, 0x96, 0xA0];
 
+const INPUT_POLL_TIMEOUT_MS: u64 = 10;
+
+const DEFAULT_SLEEP_MS: u64 = 1000;
+const SLEEP_STEP_MS: u64 = 50;
+const MIN_SLEEP_MS: u64 = 10;
+const MAX_SLEEP_MS: u64 = 5000;
+
+const STABLE_AGE: u32 = 4;
+const ELDER_AGE: u32 = 12;
+
+struct AgePalette {
+	birth: style::Color,
+	stable: style::Color,
+	elder: style::Color,
+	dying: style::Color,
+}
+
+const DEFAULT_PALETTE: AgePalette = AgePalette {
+	birth: style::Color::Green,
+	stable: style::Color::DarkCyan,
+	elder: style::Color::Blue,
+	dying: style::Color::Red,
+};
+
+fn age_tier(age: u32) -> u8 {
+	if age >= ELDER_AGE {
+		2
+	} else if age >= STABLE_AGE {
+		1
+	} else {
+		0
+	}
+}
+
+fn survivor_color(palette: &AgePalette, age: u32) -> style::Color {
+	match age_tier(age) {
+		2 => palette.elder,
+		1 => palette.stable,
+		_ => palette.birth,
+	}
+}
+
 struct Cell(u16, u16);
 
+fn cell_index(x: u16, y: u16, width: u16) -> usize {
+	y as usize * width as usize + x as usize
+}
+
+fn count_neighbors(grid: &[bool], x: u16, y: u16, width: u16, height: u16) -> u8 {
+	let mut count = 0;
+	for dy in [-1i32, 0, 1] {
+		for dx in [-1i32, 0, 1] {
+			if dx == 0 && dy == 0 {
+				continue;
+			}
+
+			let nx = (x as i32 + dx).rem_euclid(width as i32) as u16;
+			let ny = (y as i32 + dy).rem_euclid(height as i32) as u16;
+			if grid[cell_index(nx, ny, width)] {
+				count += 1;
+			}
+		}
+	}
+	count
+}
+
+fn next_generation(grid: &[bool], width: u16, height: u16) -> Vec<bool> {
+	let mut next = vec![false; grid.len()];
+	for y in 0..height {
+		for x in 0..width {
+			let alive = grid[cell_index(x, y, width)];
+			let neighbors = count_neighbors(grid, x, y, width, height);
+			next[cell_index(x, y, width)] = matches!((alive, neighbors), (true, 2) | (true, 3) | (false, 3));
+		}
+	}
+	next
+}
+
+fn advance_generation(
+	term: &mut Stdout,
+	grid: &[bool],
+	ages: &[u32],
+	pending_clear: &[bool],
+	width: u16,
+	height: u16,
+	palette: &AgePalette,
+) -> Result<(Vec<bool>, Vec<u32>, Vec<bool>)> {
+	for y in 0..height {
+		for x in 0..width {
+			let i = cell_index(x, y, width);
+			if pending_clear[i] {
+				queue_clear(term, &Cell(x, y))?;
+			}
+		}
+	}
+
+	let next = next_generation(grid, width, height);
+	let mut next_ages = vec![0u32; grid.len()];
+	let mut next_pending_clear = vec![false; grid.len()];
+
+	for y in 0..height {
+		for x in 0..width {
+			let i = cell_index(x, y, width);
+			let cell = Cell(x, y);
+
+			if grid[i] && !next[i] {
+				// Flash it red instead of clearing it immediately; queued for a
+				// real clear at the top of the next call via pending_clear.
+				queue_cell(term, &cell, palette.dying)?;
+				next_pending_clear[i] = true;
+				continue;
+			}
+
+			if !next[i] {
+				continue;
+			}
+
+			next_ages[i] = if grid[i] { ages[i] + 1 } else { 0 };
+
+			if !grid[i] {
+				queue_cell(term, &cell, palette.birth)?;
+			} else if age_tier(ages[i]) != age_tier(next_ages[i]) {
+				queue_cell(term, &cell, survivor_color(palette, next_ages[i]))?;
+			}
+		}
+	}
+
+	term.flush()?;
+	Ok((next, next_ages, next_pending_clear))
+}
+
+fn render_full(term: &mut Stdout, grid: &[bool], ages: &[u32], width: u16, height: u16, palette: &AgePalette) -> Result<()> {
+	for y in 0..height {
+		for x in 0..width {
+			let i = cell_index(x, y, width);
+			if grid[i] {
+				queue_cell(term, &Cell(x, y), survivor_color(palette, ages[i]))?;
+			}
+		}
+	}
+	term.flush()?;
+	Ok(())
+}
+
 #[cfg(windows)]
 macro_rules! assert_win32_err {
 	($res:ident, $msg:ident) => {
@@ -76,34 +219,129 @@ fn get_err_desc(errcode: minwindef::DWORD) -> String {
 }
 
 #[cfg(windows)]
-fn init_window() {
+fn init_window() -> minwindef::DWORD {
+	unsafe {
+		let stdout = processenv::GetStdHandle(winbase::STD_OUTPUT_HANDLE);
+
+		let mut original_mode: minwindef::DWORD = 0;
+		assert_win32_err! {
+			consoleapi::GetConsoleMode(stdout, &mut original_mode);
+		}
+
+		let new_mode = original_mode | wincon::ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+		assert_win32_err! {
+			consoleapi::SetConsoleMode(stdout, new_mode);
+		}
+
+		original_mode
+	}
+}
+
+#[cfg(windows)]
+fn restore_window(original_mode: minwindef::DWORD) {
 	unsafe {
-		let con_win = wincon::GetConsoleWindow();
+		let stdout = processenv::GetStdHandle(winbase::STD_OUTPUT_HANDLE);
 		assert_win32_err! {
-			winuser::ShowScrollBar(con_win, winuser::SB_VERT as i32, 0);
-		};
+			consoleapi::SetConsoleMode(stdout, original_mode);
+		}
+	}
+}
+
+#[cfg(windows)]
+fn console_size() -> Result<(u16, u16)> {
+	unsafe {
+		let stdout = processenv::GetStdHandle(winbase::STD_OUTPUT_HANDLE);
+
+		let mut info: wincon::CONSOLE_SCREEN_BUFFER_INFO = std::mem::zeroed();
+		assert_win32_err! {
+			wincon::GetConsoleScreenBufferInfo(stdout, &mut info);
+		}
+
+		let columns = (1 + info.srWindow.Right - info.srWindow.Left) as u16;
+		let rows = (1 + info.srWindow.Bottom - info.srWindow.Top) as u16;
+		Ok((columns, rows))
+	}
+}
+
+#[cfg(windows)]
+const FOREGROUND_MASK: minwindef::WORD =
+	wincon::FOREGROUND_RED | wincon::FOREGROUND_GREEN | wincon::FOREGROUND_BLUE | wincon::FOREGROUND_INTENSITY;
+
+#[cfg(windows)]
+fn color_to_bits(color: style::Color) -> minwindef::WORD {
+	match color {
+		style::Color::Black => 0,
+		style::Color::DarkRed => wincon::FOREGROUND_RED,
+		style::Color::DarkGreen => wincon::FOREGROUND_GREEN,
+		style::Color::DarkYellow => wincon::FOREGROUND_RED | wincon::FOREGROUND_GREEN,
+		style::Color::DarkBlue => wincon::FOREGROUND_BLUE,
+		style::Color::DarkMagenta => wincon::FOREGROUND_RED | wincon::FOREGROUND_BLUE,
+		style::Color::DarkCyan => wincon::FOREGROUND_GREEN | wincon::FOREGROUND_BLUE,
+		style::Color::Grey => wincon::FOREGROUND_RED | wincon::FOREGROUND_GREEN | wincon::FOREGROUND_BLUE,
+		style::Color::DarkGrey => wincon::FOREGROUND_INTENSITY,
+		style::Color::Red => wincon::FOREGROUND_RED | wincon::FOREGROUND_INTENSITY,
+		style::Color::Green => wincon::FOREGROUND_GREEN | wincon::FOREGROUND_INTENSITY,
+		style::Color::Yellow => wincon::FOREGROUND_RED | wincon::FOREGROUND_GREEN | wincon::FOREGROUND_INTENSITY,
+		style::Color::Blue => wincon::FOREGROUND_BLUE | wincon::FOREGROUND_INTENSITY,
+		style::Color::Magenta => wincon::FOREGROUND_RED | wincon::FOREGROUND_BLUE | wincon::FOREGROUND_INTENSITY,
+		style::Color::Cyan => wincon::FOREGROUND_GREEN | wincon::FOREGROUND_BLUE | wincon::FOREGROUND_INTENSITY,
+		// White and anything else crossterm can report (Rgb/AnsiValue) fall
+		// back to full-intensity white; only the 16 named colors map exactly.
+		_ => FOREGROUND_MASK,
+	}
+}
 
+// Only the foreground bits are replaced; the background (including
+// BACKGROUND_INTENSITY) is read back from the console and left untouched.
+#[cfg(windows)]
+fn set_console_color(color: style::Color) {
+	unsafe {
 		let stdout = processenv::GetStdHandle(winbase::STD_OUTPUT_HANDLE);
 
-		let info: *mut wincon::CONSOLE_SCREEN_BUFFER_INFO = std::ptr::null_mut();
+		let mut info: wincon::CONSOLE_SCREEN_BUFFER_INFO = std::mem::zeroed();
 		assert_win32_err! {
-			wincon::GetConsoleScreenBufferInfo(stdout, info);
+			wincon::GetConsoleScreenBufferInfo(stdout, &mut info);
 		}
 
-		let new_size = wincontypes::COORD {
-			X: (*info).dwSize.X - 2,
-			Y: (*info).dwSize.Y,
-		};
+		let attributes = (info.wAttributes & !FOREGROUND_MASK) | color_to_bits(color);
 		assert_win32_err! {
-			wincon::SetConsoleScreenBufferSize(stdout, new_size);
+			wincon::SetConsoleTextAttribute(stdout, attributes);
 		}
 	}
 }
 
+#[cfg(not(windows))]
+fn console_size() -> Result<(u16, u16)> {
+	terminal::size()
+}
+
+fn resize_grid(
+	grid: &[bool],
+	ages: &[u32],
+	width: u16,
+	height: u16,
+	new_width: u16,
+	new_height: u16,
+) -> (Vec<bool>, Vec<u32>) {
+	let mut resized = vec![false; new_width as usize * new_height as usize];
+	let mut resized_ages = vec![0u32; resized.len()];
+
+	let overlap_width = width.min(new_width);
+	let overlap_height = height.min(new_height);
+	for y in 0..overlap_height {
+		for x in 0..overlap_width {
+			let old_i = cell_index(x, y, width);
+			let new_i = cell_index(x, y, new_width);
+			resized[new_i] = grid[old_i];
+			resized_ages[new_i] = ages[old_i];
+		}
+	}
+	(resized, resized_ages)
+}
+
 fn init_term(term: &mut Stdout) -> Result<()> {
 	terminal::enable_raw_mode()?;
-	term.queue(terminal::SetSize(WIDTH, HEIGHT))?
-		.queue(terminal::Clear(terminal::ClearType::All))?
+	term.queue(terminal::Clear(terminal::ClearType::All))?
 		.queue(style::SetForegroundColor(style::Color::Green))?
 		.queue(style::SetAttribute(style::Attribute::NoBlink))?
 		.queue(cursor::Hide)?
@@ -112,43 +350,125 @@ fn init_term(term: &mut Stdout) -> Result<()> {
 	Ok(())
 }
 
-fn draw_cell(term: &mut Stdout, cell: &Cell) -> Result<()> {
-	term.queue(cursor::MoveTo(cell.0, cell.1))?
+fn queue_cell(term: &mut Stdout, cell: &Cell, color: style::Color) -> Result<()> {
+	#[cfg(windows)]
+	{
+		// SetConsoleTextAttribute takes effect immediately, unlike the queued
+		// glyph write below, so the pair has to be flushed together right here
+		// instead of batched with the rest of the generation's cells.
+		set_console_color(color);
+		term.queue(cursor::MoveTo(cell.0, cell.1))?
+			.write_all(CELL)?;
+		term.flush()?;
+	}
+
+	#[cfg(not(windows))]
+	term.queue(style::SetForegroundColor(color))?
+		.queue(cursor::MoveTo(cell.0, cell.1))?
 		.write_all(CELL)?;
-	term.flush()?;
+
 	Ok(())
 }
 
-fn clear_cell(term: &mut Stdout, cell: &Cell) -> Result<()> {
+fn queue_clear(term: &mut Stdout, cell: &Cell) -> Result<()> {
 	term.queue(cursor::MoveTo(cell.0, cell.1))?
 		.write_all(b" ")?;
+	Ok(())
+}
+
+fn seed_grid(term: &mut Stdout, width: u16, height: u16, palette: &AgePalette) -> Result<(Vec<bool>, Vec<u32>)> {
+	term.queue(terminal::Clear(terminal::ClearType::All))?;
+
+	let mut grid = vec![false; width as usize * height as usize];
+	let ages = vec![0u32; grid.len()];
+	for y in 0..height {
+		for x in 0..width {
+			let rng = (rand::random::<f32>() * 100f32) as i32;
+			let spawn = rng < (SPAWN_CHANCE * 100f32) as i32;
+			if !spawn {
+				continue;
+			}
+
+			grid[cell_index(x, y, width)] = true;
+			queue_cell(term, &Cell(x, y), palette.birth)?;
+		}
+	}
 	term.flush()?;
+	Ok((grid, ages))
+}
+
+fn restore_term(term: &mut Stdout) -> Result<()> {
+	term.queue(cursor::Show)?
+		.queue(terminal::EnableLineWrap)?
+		.flush()?;
+	terminal::disable_raw_mode()?;
 	Ok(())
 }
 
 //Note: Panics if run with VSCode debugger.
 fn main() -> Result<()> {
 	let mut terminal = std::io::stdout();
-	let mut cells = Vec::<Cell>::new();
 
 	#[cfg(windows)]
-	init_window();
+	let original_console_mode = init_window();
 	init_term(&mut terminal)?;
 
-	for x in 0..WIDTH {
-		for y in 0..HEIGHT {
-			let rng = (rand::random::<f32>() * 100f32) as i32;
-			let spawn = rng < (SPAWN_CHANCE * 100f32) as i32;
-			if !spawn {
-				continue;
+	let palette = DEFAULT_PALETTE;
+	let (mut width, mut height) = console_size()?;
+	let (mut grid, mut ages) = seed_grid(&mut terminal, width, height, &palette)?;
+	let mut pending_clear = vec![false; grid.len()];
+
+	let mut paused = false;
+	let mut sleep_ms = DEFAULT_SLEEP_MS;
+
+	loop {
+		let mut advance = !paused;
+
+		if event::poll(std::time::Duration::from_millis(INPUT_POLL_TIMEOUT_MS))? {
+			if let Event::Key(key) = event::read()? {
+				match key.code {
+					KeyCode::Char(' ') => paused = !paused,
+					KeyCode::Char('.') if paused => advance = true,
+					KeyCode::Char('+') => sleep_ms = (sleep_ms + SLEEP_STEP_MS).min(MAX_SLEEP_MS),
+					KeyCode::Char('-') => sleep_ms = sleep_ms.saturating_sub(SLEEP_STEP_MS).max(MIN_SLEEP_MS),
+					KeyCode::Char('r') => {
+						let (seeded, seeded_ages) = seed_grid(&mut terminal, width, height, &palette)?;
+						grid = seeded;
+						ages = seeded_ages;
+						pending_clear = vec![false; grid.len()];
+					}
+					KeyCode::Char('q') | KeyCode::Esc => {
+						restore_term(&mut terminal)?;
+						#[cfg(windows)]
+						restore_window(original_console_mode);
+						return Ok(());
+					}
+					_ => {}
+				}
 			}
+		}
 
-			let cell = Cell(x, y);
-			draw_cell(&mut terminal, &cell)?;
-			cells.push(cell);
+		let (new_width, new_height) = console_size()?;
+		if (new_width, new_height) != (width, height) {
+			let (resized, resized_ages) = resize_grid(&grid, &ages, width, height, new_width, new_height);
+			width = new_width;
+			height = new_height;
+
+			terminal.queue(terminal::Clear(terminal::ClearType::All))?;
+			render_full(&mut terminal, &resized, &resized_ages, width, height, &palette)?;
+			grid = resized;
+			ages = resized_ages;
+			pending_clear = vec![false; grid.len()];
 		}
-	}
-	loop {
-		std::thread::sleep(std::time::Duration::from_millis(1000));
+
+		if advance {
+			let (next, next_ages, next_pending_clear) =
+				advance_generation(&mut terminal, &grid, &ages, &pending_clear, width, height, &palette)?;
+			grid = next;
+			ages = next_ages;
+			pending_clear = next_pending_clear;
+		}
+
+		std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
 	}
 }